@@ -0,0 +1,79 @@
+//! Bitboard view of a [`GameState`](crate::GameState), for callers that want O(1) set
+//! operations (intersection, popcount, ...) over a position instead of re-scanning the
+//! 2D piece array.
+
+use crate::{Color, Kind, Piece, Position};
+
+const ALL_KINDS: [Kind; 6] = [Kind::Pawn, Kind::Knight, Kind::Bishop, Kind::Rook, Kind::Queen, Kind::King];
+
+/// One `u64` per piece kind and one per color, indexed by square `y * 8 + x`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct Bitboards {
+    pawns: u64,
+    knights: u64,
+    bishops: u64,
+    rooks: u64,
+    queens: u64,
+    kings: u64,
+    white: u64,
+    black: u64,
+}
+
+impl Bitboards {
+    pub(crate) fn set(&mut self, square: usize, kind: Kind, color: Color) {
+        let mask = 1u64 << square;
+        *self.kind_bitboard_mut(kind) |= mask;
+        match color {
+            Color::White => self.white |= mask,
+            Color::Black => self.black |= mask,
+        }
+    }
+
+    /// All occupied squares, regardless of piece kind or color.
+    pub fn occupied(&self) -> u64 {
+        self.white | self.black
+    }
+
+    /// All squares occupied by `color`.
+    pub fn pieces_of(&self, color: Color) -> u64 {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+
+    /// All squares occupied by a piece of the given kind, of either color.
+    pub fn kind_bitboard(&self, kind: Kind) -> u64 {
+        match kind {
+            Kind::Pawn => self.pawns,
+            Kind::Knight => self.knights,
+            Kind::Bishop => self.bishops,
+            Kind::Rook => self.rooks,
+            Kind::Queen => self.queens,
+            Kind::King => self.kings,
+        }
+    }
+
+    fn kind_bitboard_mut(&mut self, kind: Kind) -> &mut u64 {
+        match kind {
+            Kind::Pawn => &mut self.pawns,
+            Kind::Knight => &mut self.knights,
+            Kind::Bishop => &mut self.bishops,
+            Kind::Rook => &mut self.rooks,
+            Kind::Queen => &mut self.queens,
+            Kind::King => &mut self.kings,
+        }
+    }
+
+    /// The piece sitting at `position`, if any.
+    pub fn piece_at(&self, position: Position) -> Option<Piece> {
+        let Position(x, y) = position;
+        let mask = 1u64 << (y * 8 + x);
+        if self.occupied() & mask == 0 {
+            return None;
+        }
+        let color = if self.white & mask != 0 { Color::White } else { Color::Black };
+        let kind = ALL_KINDS.iter().copied().find(|&kind| self.kind_bitboard(kind) & mask != 0)?;
+        Some(Piece { kind, color, position })
+    }
+}
@@ -2,15 +2,18 @@
 
 //! Module for parsing Forsyth–Edwards Notation (FEN) in chess.
 
+mod bitboard;
+mod zobrist;
+
+pub use bitboard::Bitboards;
+
+use std::fmt;
+
 use regex::{Regex};
 
 const RANK_REGEX: &str = r"([prnbqkbnrPRNBQKBNR1-8]{1,8})/?";
 const EN_PASSANT_REGEX: &str = r"^([a-g])([36])$";
-
-const WHITE_KINGSIDE: i32 =  1 << 0;
-const WHITE_QUEENSIDE: i32 = 1 << 1;
-const BLACK_KINGSIDE: i32 =  1 << 2;
-const BLACK_QUEENSIDE: i32 = 1 << 3;
+const REMAINING_CHECKS_REGEX: &str = r"^(?:(\d)\+(\d)|\+(\d)\+(\d))$";
 
 
 macro_rules! prettyprint {
@@ -24,6 +27,7 @@ pub enum LibFenError {
     IllegalInput,
     Generic,
     RegexError(regex::Error),
+    Invalid(InvalidError),
 }
 
 impl From<regex::Error> for LibFenError {
@@ -32,6 +36,28 @@ impl From<regex::Error> for LibFenError {
     }
 }
 
+impl From<InvalidError> for LibFenError {
+    fn from(invalid_error: InvalidError) -> Self {
+        LibFenError::Invalid(invalid_error)
+    }
+}
+
+/// Diagnostics produced by [`GameState::validate`] when a parsed position is not legal.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum InvalidError {
+    /// A pawn sits on rank 1 or rank 8.
+    InvalidPawnPosition,
+    /// The two kings occupy adjacent squares.
+    NeighbouringKings,
+    /// A castling flag is set but the relevant king or rook is not on its home square.
+    InvalidCastlingRights,
+    /// The en-passant target is not empty, is on the wrong rank for the side to move,
+    /// or has no enemy pawn directly in front of it.
+    InvalidEnPassant,
+    /// Either side does not have exactly one king on the board.
+    WrongKingCount,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Color {
     White,
@@ -58,17 +84,84 @@ pub struct Piece {
     position: Position,
 }
 
+/// Per-side castling rights, recorded as the file (0-7, a-h) of the rook involved rather
+/// than a simple flag. This is what lets Chess960 (X-FEN/Shredder-FEN) positions, whose
+/// rooks are not necessarily on the a- and h-files, be round-tripped.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+struct CastlingRights {
+    white_kingside: Option<usize>,
+    white_queenside: Option<usize>,
+    black_kingside: Option<usize>,
+    black_queenside: Option<usize>,
+}
+
+/// Captured-piece counts held in hand for one side in a Crazyhouse game.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct Pocket {
+    pawns: u8,
+    knights: u8,
+    bishops: u8,
+    rooks: u8,
+    queens: u8,
+}
+
+impl Pocket {
+    pub fn count(&self, kind: Kind) -> u8 {
+        match kind {
+            Kind::Pawn => self.pawns,
+            Kind::Knight => self.knights,
+            Kind::Bishop => self.bishops,
+            Kind::Rook => self.rooks,
+            Kind::Queen => self.queens,
+            Kind::King => 0
+        }
+    }
+
+    fn increment(&mut self, kind: Kind) {
+        match kind {
+            Kind::Pawn => self.pawns += 1,
+            Kind::Knight => self.knights += 1,
+            Kind::Bishop => self.bishops += 1,
+            Kind::Rook => self.rooks += 1,
+            Kind::Queen => self.queens += 1,
+            Kind::King => {}
+        }
+    }
+}
+
+/// Checks each side still has to deliver to win a Three-Check game.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct RemainingChecks {
+    white: u8,
+    black: u8,
+}
+
+impl RemainingChecks {
+    pub fn remaining(&self, color: Color) -> u8 {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct GameState {
     /// organization: [y][x]
     pieces: [[Option<Piece>; 8]; 8],
     active_color: Color,
-    castling_availability: i32,
+    castling_availability: CastlingRights,
     en_passant: Option<Position>,
     /// This is the number of halfmoves since the last capture or pawn advance.
     half_move_clock: i32,
     /// The number of the full move. It starts at 1, and is incremented after Black's move
     full_move_clock: i32,
+    /// Crazyhouse: captured pieces White holds in hand, ready to be dropped.
+    white_pocket: Pocket,
+    /// Crazyhouse: captured pieces Black holds in hand, ready to be dropped.
+    black_pocket: Pocket,
+    /// Three-Check: checks each side still has to deliver to win, if this is a Three-Check game.
+    remaining_checks: Option<RemainingChecks>,
 }
 
 impl GameState {
@@ -76,18 +169,270 @@ impl GameState {
         GameState {
             pieces: [[None; 8]; 8],
             active_color: Color::White,
-            castling_availability: 0,
+            castling_availability: CastlingRights::default(),
             en_passant: None,
             half_move_clock: 0,
-            full_move_clock: 1
+            full_move_clock: 1,
+            white_pocket: Pocket::default(),
+            black_pocket: Pocket::default(),
+            remaining_checks: None,
+        }
+    }
+
+    /// The Crazyhouse pocket for `color`, i.e. the captured pieces it holds in hand.
+    pub fn pocket(&self, color: Color) -> Pocket {
+        match color {
+            Color::White => self.white_pocket,
+            Color::Black => self.black_pocket
         }
     }
+
+    /// The Three-Check remaining-checks counters, if the parsed FEN carried that field.
+    pub fn remaining_checks(&self) -> Option<RemainingChecks> {
+        self.remaining_checks
+    }
+
+    /// A bitboard view of the position, for callers that want O(1) set operations over
+    /// the board instead of re-scanning the `[[Option<Piece>; 8]; 8]` array.
+    pub fn bitboards(&self) -> Bitboards {
+        let mut bitboards = Bitboards::default();
+        for (y, rank) in self.pieces.iter().enumerate() {
+            for (x, square) in rank.iter().enumerate() {
+                if let Some(piece) = square {
+                    bitboards.set(y * 8 + x, piece.kind, piece.color);
+                }
+            }
+        }
+        bitboards
+    }
+
+    /// A Zobrist hash of the position, suitable as a transposition-table or
+    /// repetition-detection key. Stable across runs and machines.
+    pub fn zobrist_hash(&self) -> u64 {
+        zobrist::hash(self)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let ranks: Vec<String> = (0..=7).rev()
+            .map(|y| rank_to_fen(&self.pieces[y]))
+            .collect();
+
+        let active_color = match self.active_color {
+            Color::White => "w",
+            Color::Black => "b"
+        };
+
+        format!("{} {} {} {} {} {}",
+            ranks.join("/"),
+            active_color,
+            castling_availability_to_fen(self.castling_availability),
+            en_passant_to_fen(self.en_passant),
+            self.half_move_clock,
+            self.full_move_clock)
+    }
+
+    /// Checks that a parsed position is actually legal, as opposed to merely well-formed FEN.
+    pub fn validate(&self) -> Result<(), InvalidError> {
+        self.validate_pawn_positions()?;
+        let (white_king, black_king) = self.validate_king_count()?;
+        validate_kings_not_neighbouring(white_king, black_king)?;
+        self.validate_castling_rights()?;
+        self.validate_en_passant()?;
+        Ok(())
+    }
+
+    fn validate_pawn_positions(&self) -> Result<(), InvalidError> {
+        for rank in self.pieces.iter() {
+            for square in rank {
+                if let Some(Piece { kind: Kind::Pawn, position, .. }) = square {
+                    if position.1 == 0 || position.1 == 7 {
+                        return Err(InvalidError::InvalidPawnPosition);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_king_count(&self) -> Result<(Position, Position), InvalidError> {
+        let mut white_king = None;
+        let mut black_king = None;
+        for rank in self.pieces.iter() {
+            for square in rank {
+                if let Some(Piece { kind: Kind::King, color, position }) = square {
+                    let slot = match color {
+                        Color::White => &mut white_king,
+                        Color::Black => &mut black_king,
+                    };
+                    if slot.is_some() {
+                        return Err(InvalidError::WrongKingCount);
+                    }
+                    *slot = Some(*position);
+                }
+            }
+        }
+        match (white_king, black_king) {
+            (Some(w), Some(b)) => Ok((w, b)),
+            _ => Err(InvalidError::WrongKingCount)
+        }
+    }
+
+    fn validate_castling_rights(&self) -> Result<(), InvalidError> {
+        let has_king_on_home_rank = |color: Color| find_king_file(&self.pieces, color).is_some();
+        let has_rook_on_home_rank = |color: Color, file: usize| {
+            match self.pieces[home_rank(color)][file] {
+                Some(Piece { kind: Kind::Rook, color: piece_color, .. }) => piece_color == color,
+                _ => false
+            }
+        };
+
+        let checks = [
+            (self.castling_availability.white_kingside, Color::White),
+            (self.castling_availability.white_queenside, Color::White),
+            (self.castling_availability.black_kingside, Color::Black),
+            (self.castling_availability.black_queenside, Color::Black),
+        ];
+
+        for (rook_file, color) in checks.iter().copied() {
+            let rook_file = match rook_file {
+                Some(rook_file) => rook_file,
+                None => continue
+            };
+            if !has_king_on_home_rank(color) || !has_rook_on_home_rank(color, rook_file) {
+                return Err(InvalidError::InvalidCastlingRights);
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_en_passant(&self) -> Result<(), InvalidError> {
+        let ep = match self.en_passant {
+            Some(ep) => ep,
+            None => return Ok(())
+        };
+        let Position(x, rank) = ep;
+
+        let expected_rank = match self.active_color {
+            Color::Black => 3,
+            Color::White => 6,
+        };
+        if rank != expected_rank {
+            return Err(InvalidError::InvalidEnPassant);
+        }
+
+        let target_square_idx = rank - 1;
+        if self.pieces[target_square_idx][x].is_some() {
+            return Err(InvalidError::InvalidEnPassant);
+        }
+
+        let (pawn_idx, pawn_color) = match self.active_color {
+            Color::Black => (rank, Color::White),
+            Color::White => (rank - 2, Color::Black),
+        };
+        match self.pieces[pawn_idx][x] {
+            Some(Piece { kind: Kind::Pawn, color, .. }) if color == pawn_color => Ok(()),
+            _ => Err(InvalidError::InvalidEnPassant)
+        }
+    }
+}
+
+fn validate_kings_not_neighbouring(white_king: Position, black_king: Position) -> Result<(), InvalidError> {
+    let dx = (white_king.0 as i32 - black_king.0 as i32).abs();
+    let dy = (white_king.1 as i32 - black_king.1 as i32).abs();
+    if dx <= 1 && dy <= 1 {
+        return Err(InvalidError::NeighbouringKings);
+    }
+    Ok(())
+}
+
+impl fmt::Display for GameState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_fen())
+    }
+}
+
+fn rank_to_fen(rank: &[Option<Piece>; 8]) -> String {
+    let mut result = String::new();
+    let mut empty_run = 0;
+
+    for square in rank {
+        match square {
+            Some(piece) => {
+                if empty_run > 0 {
+                    result.push_str(&empty_run.to_string());
+                    empty_run = 0;
+                }
+                result.push(piece_to_char(piece));
+            },
+            None => empty_run += 1
+        }
+    }
+    if empty_run > 0 {
+        result.push_str(&empty_run.to_string());
+    }
+
+    return result;
+}
+
+fn piece_to_char(piece: &Piece) -> char {
+    let c = match piece.kind {
+        Kind::Pawn => 'p',
+        Kind::Rook => 'r',
+        Kind::Knight => 'n',
+        Kind::Bishop => 'b',
+        Kind::Queen => 'q',
+        Kind::King => 'k',
+    };
+    match piece.color {
+        Color::White => c.to_ascii_uppercase(),
+        Color::Black => c
+    }
+}
+
+fn castling_availability_to_fen(castling_availability: CastlingRights) -> String {
+    let mut result = String::new();
+    push_castling_char(&mut result, castling_availability.white_kingside, 7, 'K');
+    push_castling_char(&mut result, castling_availability.white_queenside, 0, 'Q');
+    push_castling_char(&mut result, castling_availability.black_kingside, 7, 'k');
+    push_castling_char(&mut result, castling_availability.black_queenside, 0, 'q');
+    if result.is_empty() {
+        result.push('-');
+    }
+    return result;
+}
+
+/// Emits the classic `KQkq` letter when the right's rook sits on its classic file (h/a),
+/// and the Shredder-FEN file letter otherwise, so Chess960 rights round-trip correctly.
+fn push_castling_char(result: &mut String, rook_file: Option<usize>, classic_file: usize, classic_char: char) {
+    if let Some(rook_file) = rook_file {
+        if rook_file == classic_file {
+            result.push(classic_char);
+        } else {
+            let file_letter = (b'a' + rook_file as u8) as char;
+            result.push(if classic_char.is_ascii_uppercase() { file_letter.to_ascii_uppercase() } else { file_letter });
+        }
+    }
+}
+
+fn en_passant_to_fen(en_passant: Option<Position>) -> String {
+    match en_passant {
+        Some(Position(x, y)) => format!("{}{}", (b'a' + x as u8) as char, y),
+        None => "-".to_string()
+    }
 }
 
 pub fn parse(fen_str: &str) -> Result<GameState, LibFenError> {
     do_parse(fen_str, GameState::blank(), true)
 }
 
+/// Like [`parse`], but additionally rejects positions that parse fine as FEN but are not
+/// actually legal (see [`GameState::validate`]).
+pub fn parse_validated(fen_str: &str) -> Result<GameState, LibFenError> {
+    let game_state = parse(fen_str)?;
+    game_state.validate()?;
+    Ok(game_state)
+}
+
 pub fn parse_or_default(fen_str: &str) -> GameState {
     parse_or_else(fen_str, GameState::blank())
 }
@@ -97,31 +442,113 @@ pub fn parse_or_else(fen_str: &str, defaults: GameState) -> GameState {
 }
 
 fn do_parse(fen_str: &str, defaults: GameState, strict: bool) -> Result<GameState, LibFenError> {
-    let mut split = fen_str.split_whitespace();
+    let mut split = fen_str.split_whitespace().peekable();
     let mut game_state = defaults;
 
-    let pieces = parse_ranks(split.next());
-    let active_color = parse_active_color(split.next());
-    let castling_availability = parse_castling_availabilty(split.next());
-    let en_passant = parse_en_passant(split.next());
-    let half_move_clock = parse_move_clock(split.next());
-    let full_move_clock = parse_move_clock(split.next());
+    let ranks_and_pocket = split.next();
+    let (ranks, pocket) = match ranks_and_pocket {
+        Some(token) => {
+            let (ranks, pocket) = split_off_pocket(token);
+            (Some(ranks), pocket)
+        },
+        None => (None, None)
+    };
 
-    let pieces = if strict { pieces? } else { pieces.unwrap_or(Vec::new()) };
+    let pieces = parse_ranks(ranks);
+    let pieces = if strict { pieces? } else { pieces.unwrap_or_default() };
 
     // organization: [y][x]
     for piece in pieces {
         game_state.pieces[piece.position.1][piece.position.0] = Some(piece);
     }
-    game_state.active_color = if strict { active_color? } else { defaults.active_color };
-    game_state.castling_availability = if strict { castling_availability? } else { defaults.castling_availability };
-    game_state.en_passant = if strict { en_passant? } else { defaults.en_passant };
-    game_state.half_move_clock = if strict { half_move_clock? } else { defaults.half_move_clock };
-    game_state.full_move_clock = if strict { full_move_clock? } else { defaults.full_move_clock };
+
+    let (white_pocket, black_pocket) = match pocket {
+        Some(pocket) => parse_pocket(pocket),
+        None => (defaults.white_pocket, defaults.black_pocket)
+    };
+
+    let active_color = parse_active_color(split.next());
+    let castling_availability = parse_castling_availabilty(split.next(), &game_state.pieces);
+    let en_passant = parse_en_passant(split.next());
+    let remaining_checks = parse_remaining_checks(&mut split);
+    let half_move_clock = parse_move_clock(split.next());
+    let full_move_clock = parse_move_clock(split.next());
+
+    // In relaxed mode, a field that was present and parsed successfully is used as-is;
+    // only a field that was missing or malformed falls back to `defaults`.
+    game_state.active_color = if strict { active_color? } else { active_color.unwrap_or(defaults.active_color) };
+    game_state.castling_availability = if strict { castling_availability? } else { castling_availability.unwrap_or(defaults.castling_availability) };
+    game_state.en_passant = if strict { en_passant? } else { en_passant.unwrap_or(defaults.en_passant) };
+    game_state.remaining_checks = if strict { remaining_checks } else { remaining_checks.or(defaults.remaining_checks) };
+    game_state.half_move_clock = if strict { half_move_clock? } else { half_move_clock.unwrap_or(defaults.half_move_clock) };
+    game_state.full_move_clock = if strict { full_move_clock? } else { full_move_clock.unwrap_or(defaults.full_move_clock) };
+    game_state.white_pocket = white_pocket;
+    game_state.black_pocket = black_pocket;
 
     return Ok(game_state);
 }
 
+/// Splits a Crazyhouse pocket specification off the board field, recognizing both the
+/// bracketed form (`RNBQKBNR[Pp]`) and the trailing-rank form (`RNBQKBNR/Pp`).
+fn split_off_pocket(board: &str) -> (&str, Option<&str>) {
+    if let Some(bracket_start) = board.find('[') {
+        if board.ends_with(']') {
+            return (&board[..bracket_start], Some(&board[bracket_start + 1..board.len() - 1]));
+        }
+    }
+    if board.matches('/').count() == 8 {
+        if let Some(slash_idx) = board.rfind('/') {
+            return (&board[..slash_idx], Some(&board[slash_idx + 1..]));
+        }
+    }
+    (board, None)
+}
+
+fn parse_pocket(pocket: &str) -> (Pocket, Pocket) {
+    let mut white = Pocket::default();
+    let mut black = Pocket::default();
+    for c in pocket.chars() {
+        let kind = match c.to_ascii_lowercase() {
+            'p' => Kind::Pawn,
+            'n' => Kind::Knight,
+            'b' => Kind::Bishop,
+            'r' => Kind::Rook,
+            'q' => Kind::Queen,
+            _ => continue
+        };
+        if c.is_ascii_uppercase() {
+            white.increment(kind);
+        } else {
+            black.increment(kind);
+        }
+    }
+    (white, black)
+}
+
+/// Detects and consumes an optional Three-Check remaining-checks field, in either
+/// `3+3` (checks left for white+black) or `+0+0` (checks delivered) form. Does nothing
+/// if the next token doesn't look like a checks field, so standard FENs parse unchanged.
+fn parse_remaining_checks(split: &mut std::iter::Peekable<std::str::SplitWhitespace<'_>>) -> Option<RemainingChecks> {
+    let re = Regex::new(REMAINING_CHECKS_REGEX).ok()?;
+    let candidate = *split.peek()?;
+    let cap = re.captures(candidate)?;
+
+    split.next();
+
+    if let (Some(white), Some(black)) = (cap.get(1), cap.get(2)) {
+        let white = white.as_str().parse().ok()?;
+        let black = black.as_str().parse().ok()?;
+        return Some(RemainingChecks { white, black });
+    }
+
+    let delivered_white: u8 = cap.get(3)?.as_str().parse().ok()?;
+    let delivered_black: u8 = cap.get(4)?.as_str().parse().ok()?;
+    Some(RemainingChecks {
+        white: 3u8.saturating_sub(delivered_white),
+        black: 3u8.saturating_sub(delivered_black),
+    })
+}
+
 fn parse_ranks(ranks: Option<&str>) -> Result<Vec<Piece>, LibFenError> {
     let ranks = ranks.ok_or(LibFenError::IncompleteFen)?;
 
@@ -179,28 +606,83 @@ fn parse_active_color(input: Option<&str>) -> Result<Color, LibFenError> {
     }
 }
 
-fn parse_castling_availabilty(input: Option<&str>) -> Result<i32, LibFenError> {
+/// Parses the castling availability field. Accepts classic `KQkq`, X-FEN (where `K`/`Q`
+/// denote the outermost rook on the king's side, resolved from the board so Chess960
+/// starting positions work), and Shredder-FEN (explicit rook file letters, uppercase for
+/// White, lowercase for Black). Rights may appear in any order; duplicates are ignored.
+fn parse_castling_availabilty(input: Option<&str>, board: &[[Option<Piece>; 8]; 8]) -> Result<CastlingRights, LibFenError> {
     let input = input.ok_or(LibFenError::IncompleteFen)?;
 
-    let mut value = 0;
-    if let Some(_) = input.find('K') {
-        value |= WHITE_KINGSIDE;
+    let mut rights = CastlingRights::default();
+    if input == "-" {
+        return Ok(rights);
     }
-    if let Some(_) = input.find('k') {
-        value |= BLACK_KINGSIDE;
+
+    for c in input.chars() {
+        match c {
+            'K' => rights.white_kingside = rights.white_kingside.or(Some(resolve_xfen_file(board, Color::White, true))),
+            'Q' => rights.white_queenside = rights.white_queenside.or(Some(resolve_xfen_file(board, Color::White, false))),
+            'k' => rights.black_kingside = rights.black_kingside.or(Some(resolve_xfen_file(board, Color::Black, true))),
+            'q' => rights.black_queenside = rights.black_queenside.or(Some(resolve_xfen_file(board, Color::Black, false))),
+            'A'..='H' => set_shredder_right(&mut rights, board, Color::White, (c as u8 - b'A') as usize),
+            'a'..='h' => set_shredder_right(&mut rights, board, Color::Black, (c as u8 - b'a') as usize),
+            _ => return Err(LibFenError::IllegalInput)
+        }
     }
-    if let Some(_) = input.find('Q') {
-        value |= WHITE_QUEENSIDE;
+
+    return Ok(rights);
+}
+
+/// Resolves a classic/X-FEN `K`/`Q` castling letter to the file of the outermost rook on
+/// that side of the king, falling back to the classic h-/a-file rook when the king can't
+/// be found on its home rank.
+fn resolve_xfen_file(board: &[[Option<Piece>; 8]; 8], color: Color, kingside: bool) -> usize {
+    let classic_file = if kingside { 7 } else { 0 };
+    let king_file = match find_king_file(board, color) {
+        Some(king_file) => king_file,
+        None => return classic_file
+    };
+
+    let y = home_rank(color);
+    let rook_files = (0..8).filter(|&x| matches!(board[y][x], Some(Piece { kind: Kind::Rook, color: c, .. }) if c == color));
+    let outermost = if kingside {
+        rook_files.filter(|&x| x > king_file).max()
+    } else {
+        rook_files.filter(|&x| x < king_file).min()
+    };
+    outermost.unwrap_or(classic_file)
+}
+
+fn set_shredder_right(rights: &mut CastlingRights, board: &[[Option<Piece>; 8]; 8], color: Color, file: usize) {
+    let king_file = find_king_file(board, color).unwrap_or(4);
+    let kingside = file > king_file;
+    match (color, kingside) {
+        (Color::White, true) => rights.white_kingside = rights.white_kingside.or(Some(file)),
+        (Color::White, false) => rights.white_queenside = rights.white_queenside.or(Some(file)),
+        (Color::Black, true) => rights.black_kingside = rights.black_kingside.or(Some(file)),
+        (Color::Black, false) => rights.black_queenside = rights.black_queenside.or(Some(file)),
     }
-    if let Some(_) = input.find('q') {
-        value |= BLACK_QUEENSIDE;
+}
+
+fn home_rank(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 7
     }
-    return Ok(value);
+}
+
+fn find_king_file(board: &[[Option<Piece>; 8]; 8], color: Color) -> Option<usize> {
+    let y = home_rank(color);
+    (0..8).find(|&x| matches!(board[y][x], Some(Piece { kind: Kind::King, color: c, .. }) if c == color))
 }
 
 fn parse_en_passant(input: Option<&str>) -> Result<Option<Position>, LibFenError> {
     let input = input.ok_or(LibFenError::IncompleteFen)?;
 
+    if input == "-" || input == "0" {
+        return Ok(None);
+    }
+
     let re = Regex::new(EN_PASSANT_REGEX)?;
     let cap = re.captures(input).ok_or(LibFenError::Generic)?;
 
@@ -224,7 +706,7 @@ fn parse_move_clock(input: Option<&str>) -> Result<i32, LibFenError> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse, Kind, Color, Position};
+    use crate::{parse, parse_validated, InvalidError, Kind, Color, Position};
 
     macro_rules! test_piece {
         ( $game_state:expr, $kind:expr, $color:expr, $position:expr ) => {
@@ -346,4 +828,241 @@ mod tests {
         test_piece!(game_state, Kind::Knight, Color::Black, Position(6, 7));
         test_piece!(game_state, Kind::Rook, Color::Black, Position(7, 7));
     }
+
+    #[test]
+    fn to_fen_roundtrip_starting_position() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.to_fen(), fen);
+    }
+
+    #[test]
+    fn to_fen_roundtrip_midgame_with_en_passant() {
+        let fen = "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.to_fen(), fen);
+    }
+
+    #[test]
+    fn display_matches_to_fen() {
+        let fen = "8/8/8/8/8/8/8/8 w - - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(format!("{}", game_state), game_state.to_fen());
+    }
+
+    #[test]
+    fn validate_accepts_starting_position() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert!(game_state.validate().is_ok());
+        assert!(parse_validated(fen).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_missing_kings() {
+        let fen = "8/8/8/8/8/8/8/8 w - - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.validate().unwrap_err(), InvalidError::WrongKingCount);
+        assert!(parse_validated(fen).is_err());
+    }
+
+    #[test]
+    fn validate_rejects_pawn_on_back_rank() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/PNBQKBNR w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.validate().unwrap_err(), InvalidError::InvalidPawnPosition);
+    }
+
+    #[test]
+    fn validate_rejects_neighbouring_kings() {
+        let fen = "8/8/8/8/8/8/4kK2/8 w - - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.validate().unwrap_err(), InvalidError::NeighbouringKings);
+    }
+
+    #[test]
+    fn validate_rejects_castling_rights_without_rook() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/1NBQKBNR w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.validate().unwrap_err(), InvalidError::InvalidCastlingRights);
+    }
+
+    #[test]
+    fn validate_accepts_en_passant_after_double_push() {
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert!(game_state.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_en_passant_without_pawn() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq e3 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.validate().unwrap_err(), InvalidError::InvalidEnPassant);
+    }
+
+    #[test]
+    fn shredder_fen_castling_resolves_rook_files() {
+        let fen = "nrkbqrbn/pppppppp/8/8/8/8/PPPPPPPP/NRKBQRBN w BFbf - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert!(game_state.validate().is_ok());
+    }
+
+    #[test]
+    fn xfen_and_shredder_fen_agree_on_rook_files() {
+        let xfen = "nrkbqrbn/pppppppp/8/8/8/8/PPPPPPPP/NRKBQRBN w KQkq - 0 1";
+        let shredder = "nrkbqrbn/pppppppp/8/8/8/8/PPPPPPPP/NRKBQRBN w BFbf - 0 1";
+        let from_xfen = parse(xfen).ok().unwrap();
+        let from_shredder = parse(shredder).ok().unwrap();
+        assert_eq!(from_xfen.castling_availability, from_shredder.castling_availability);
+    }
+
+    #[test]
+    fn castling_to_fen_is_idempotent_for_chess960() {
+        let fen = "nrkbqrbn/pppppppp/8/8/8/8/PPPPPPPP/NRKBQRBN w BFbf - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        let reparsed = parse(&game_state.to_fen()).ok().unwrap();
+        assert_eq!(reparsed.to_fen(), game_state.to_fen());
+    }
+
+    #[test]
+    fn duplicate_castling_letters_are_ignored() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQKQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    }
+
+    #[test]
+    fn crazyhouse_pocket_bracket_form() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[Ppn] w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.pocket(Color::White).count(Kind::Pawn), 1);
+        assert_eq!(game_state.pocket(Color::Black).count(Kind::Pawn), 1);
+        assert_eq!(game_state.pocket(Color::Black).count(Kind::Knight), 1);
+        assert_eq!(game_state.pocket(Color::White).count(Kind::Knight), 0);
+        test_piece!(game_state, Kind::Rook, Color::White, Position(0, 0));
+    }
+
+    #[test]
+    fn crazyhouse_pocket_trailing_rank_form() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR/Qr w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.pocket(Color::White).count(Kind::Queen), 1);
+        assert_eq!(game_state.pocket(Color::Black).count(Kind::Rook), 1);
+        test_piece!(game_state, Kind::Rook, Color::White, Position(0, 0));
+    }
+
+    #[test]
+    fn standard_fen_has_no_pocket() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert_eq!(game_state.pocket(Color::White).count(Kind::Queen), 0);
+        assert_eq!(game_state.pocket(Color::Black).count(Kind::Queen), 0);
+    }
+
+    #[test]
+    fn three_check_checks_left_form() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        let checks = game_state.remaining_checks().unwrap();
+        assert_eq!(checks.remaining(Color::White), 3);
+        assert_eq!(checks.remaining(Color::Black), 3);
+    }
+
+    #[test]
+    fn three_check_checks_delivered_form() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - +1+2 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        let checks = game_state.remaining_checks().unwrap();
+        assert_eq!(checks.remaining(Color::White), 2);
+        assert_eq!(checks.remaining(Color::Black), 1);
+    }
+
+    #[test]
+    fn standard_fen_has_no_remaining_checks() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        assert!(game_state.remaining_checks().is_none());
+    }
+
+    #[test]
+    fn bitboards_occupied_and_per_color() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        let bitboards = game_state.bitboards();
+
+        assert_eq!(bitboards.occupied().count_ones(), 32);
+        assert_eq!(bitboards.pieces_of(Color::White).count_ones(), 16);
+        assert_eq!(bitboards.pieces_of(Color::Black).count_ones(), 16);
+        assert_eq!(bitboards.kind_bitboard(Kind::Pawn).count_ones(), 16);
+        assert_eq!(bitboards.kind_bitboard(Kind::King).count_ones(), 2);
+    }
+
+    #[test]
+    fn bitboards_piece_at_matches_array() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let game_state = parse(fen).ok().unwrap();
+        let bitboards = game_state.bitboards();
+
+        let piece = bitboards.piece_at(Position(4, 0)).unwrap();
+        assert!(piece.kind == Kind::King && piece.color == Color::White);
+        assert!(bitboards.piece_at(Position(4, 3)).is_none());
+    }
+
+    #[test]
+    fn zobrist_hash_is_deterministic() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let a = parse(fen).ok().unwrap();
+        let b = parse(fen).ok().unwrap();
+        assert_eq!(a.zobrist_hash(), b.zobrist_hash());
+    }
+
+    #[test]
+    fn zobrist_hash_differs_for_different_positions() {
+        let starting = parse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").ok().unwrap();
+        let after_e4 = parse("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").ok().unwrap();
+        assert_ne!(starting.zobrist_hash(), after_e4.zobrist_hash());
+    }
+
+    #[test]
+    fn zobrist_hash_accounts_for_side_to_move() {
+        let white_to_move = parse("8/8/8/8/8/8/4K3/4k3 w - - 0 1").ok().unwrap();
+        let black_to_move = parse("8/8/8/8/8/8/4K3/4k3 b - - 0 1").ok().unwrap();
+        assert_ne!(white_to_move.zobrist_hash(), black_to_move.zobrist_hash());
+    }
+
+    #[test]
+    fn relaxed_parse_fills_in_missing_fields_with_defaults() {
+        use crate::parse_or_default;
+
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR";
+        let game_state = parse_or_default(fen);
+        assert_eq!(game_state.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1");
+    }
+
+    #[test]
+    fn relaxed_parse_keeps_fields_present_in_the_input() {
+        use crate::parse_or_default;
+
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 1 2";
+        let game_state = parse_or_default(fen);
+        assert_eq!(game_state.to_fen(), fen);
+    }
+
+    #[test]
+    fn relaxed_parse_tolerates_runs_of_whitespace() {
+        use crate::parse_or_default;
+
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR  b   KQkq  -  1  2";
+        let game_state = parse_or_default(fen);
+        assert_eq!(game_state.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 1 2");
+    }
+
+    #[test]
+    fn en_passant_accepts_zero_as_well_as_dash() {
+        let with_dash = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let with_zero = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq 0 0 1";
+        assert_eq!(parse(with_dash).ok().unwrap().en_passant, None);
+        assert_eq!(parse(with_zero).ok().unwrap().en_passant, None);
+    }
 }
@@ -0,0 +1,99 @@
+//! Zobrist hashing for [`GameState`](crate::GameState), so a parsed position can be
+//! reduced to a stable `u64` key for transposition tables and repetition detection.
+//!
+//! The key table is a compile-time constant seeded with a fixed value, so hashes are
+//! reproducible across runs and machines.
+
+use crate::{Color, GameState, Kind, Position};
+
+const PIECE_KEYS: usize = 12 * 64;
+const SIDE_TO_MOVE_KEY: usize = PIECE_KEYS;
+const CASTLING_KEYS: usize = SIDE_TO_MOVE_KEY + 1;
+const EN_PASSANT_KEYS: usize = CASTLING_KEYS + 4;
+const TOTAL_KEYS: usize = EN_PASSANT_KEYS + 8;
+
+const ZOBRIST_SEED: u64 = 0x5EED_1234_ABCD_EF01;
+
+const fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const fn build_keys() -> [u64; TOTAL_KEYS] {
+    let mut state = ZOBRIST_SEED;
+    let mut keys = [0u64; TOTAL_KEYS];
+    let mut i = 0;
+    while i < TOTAL_KEYS {
+        keys[i] = splitmix64(&mut state);
+        i += 1;
+    }
+    keys
+}
+
+const ZOBRIST_KEYS: [u64; TOTAL_KEYS] = build_keys();
+
+const fn piece_plane(kind: Kind, color: Color) -> usize {
+    let kind_plane = match kind {
+        Kind::Pawn => 0,
+        Kind::Knight => 1,
+        Kind::Bishop => 2,
+        Kind::Rook => 3,
+        Kind::Queen => 4,
+        Kind::King => 5,
+    };
+    let color_plane = match color {
+        Color::White => 0,
+        Color::Black => 1,
+    };
+    color_plane * 6 + kind_plane
+}
+
+fn piece_key(kind: Kind, color: Color, square: usize) -> u64 {
+    ZOBRIST_KEYS[piece_plane(kind, color) * 64 + square]
+}
+
+fn side_to_move_key() -> u64 {
+    ZOBRIST_KEYS[SIDE_TO_MOVE_KEY]
+}
+
+fn castling_key(corner: usize) -> u64 {
+    ZOBRIST_KEYS[CASTLING_KEYS + corner]
+}
+
+fn en_passant_file_key(file: usize) -> u64 {
+    ZOBRIST_KEYS[EN_PASSANT_KEYS + file]
+}
+
+pub(crate) fn hash(game_state: &GameState) -> u64 {
+    let mut hash = 0u64;
+
+    for (y, rank) in game_state.pieces.iter().enumerate() {
+        for (x, square) in rank.iter().enumerate() {
+            if let Some(piece) = square {
+                hash ^= piece_key(piece.kind, piece.color, y * 8 + x);
+            }
+        }
+    }
+
+    if game_state.active_color == Color::Black {
+        hash ^= side_to_move_key();
+    }
+
+    let castling = &game_state.castling_availability;
+    for (corner, right) in [castling.white_kingside, castling.white_queenside, castling.black_kingside, castling.black_queenside]
+        .iter()
+        .enumerate() {
+        if right.is_some() {
+            hash ^= castling_key(corner);
+        }
+    }
+
+    if let Some(Position(file, _)) = game_state.en_passant {
+        hash ^= en_passant_file_key(file);
+    }
+
+    hash
+}